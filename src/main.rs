@@ -1,9 +1,26 @@
 use anyhow::Result;
-use clap::Parser;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::stream::{FuturesUnordered, StreamExt};
+use hmac::{Hmac, Mac};
 use indicatif::{ProgressBar, ProgressStyle};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use opentelemetry::sdk::trace as sdktrace;
+use opentelemetry::sdk::Resource;
 use opentelemetry::trace::Tracer;
 use opentelemetry::trace::TracerProvider;
+use opentelemetry::metrics::{Histogram, Unit};
 use opentelemetry::KeyValue;
+use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
 
 /// `gh-action-trace` is used to create traces for GitHub Action runs
 /// by talking to the GitHub API and getting the metadata. This is
@@ -25,6 +42,253 @@ struct Opts {
     /// cause timeouts
     #[clap(short, long)]
     token: Option<String>,
+    /// Backend used to export the generated traces
+    #[clap(long, value_enum, default_value_t = Exporter::Jaeger)]
+    exporter: Exporter,
+    /// Endpoint of the collector the selected exporter should ship spans to
+    #[clap(long)]
+    endpoint: Option<String>,
+    /// Jaeger transport: the UDP `agent` or the HTTP `collector`
+    #[clap(long, value_enum, default_value_t = JaegerMode::Agent)]
+    jaeger_mode: JaegerMode,
+    /// Jaeger agent endpoint (host:port) when `--jaeger-mode agent`
+    #[clap(long)]
+    agent_endpoint: Option<String>,
+    /// Jaeger collector endpoint (URL) when `--jaeger-mode collector`
+    #[clap(long)]
+    collector_endpoint: Option<String>,
+    /// Basic-auth username for the Jaeger collector
+    #[clap(long)]
+    collector_user: Option<String>,
+    /// Basic-auth password for the Jaeger collector
+    #[clap(long)]
+    collector_password: Option<String>,
+    /// Maximum number of GitHub API requests to keep in flight at once.
+    /// Caps the fan-out so large repos don't trip GitHub's rate limits.
+    #[clap(long, default_value_t = 10, value_parser = parse_concurrency)]
+    concurrency: usize,
+    /// Path to a SQLite database caching fetched workflow/run/job
+    /// metadata. When set, only runs newer than the newest cached run
+    /// are fetched, so repeated invocations stay cheap and idempotent.
+    #[clap(long)]
+    db: Option<PathBuf>,
+    /// Re-export traces straight from the `--db` cache without making any
+    /// GitHub API calls. Useful for pointing historical runs at a new
+    /// exporter.
+    #[clap(long, requires = "db")]
+    from_cache: bool,
+    /// Also record OpenTelemetry histogram metrics for run and job
+    /// durations, exported through an OTLP metrics pipeline, so build
+    /// times can be charted and alerted on over time.
+    #[clap(long)]
+    metrics: bool,
+    /// Endpoint for the OTLP metrics pipeline. Kept separate from the
+    /// trace `--endpoint` so traces and metrics can target different
+    /// collectors (e.g. a Jaeger agent vs. an OTLP metrics backend).
+    #[clap(long)]
+    metrics_endpoint: Option<String>,
+    /// Restrict metrics to these workflow names (by `name`). Repeatable;
+    /// when omitted, every workflow is measured.
+    #[clap(long = "workflow")]
+    workflows: Vec<String>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// Sub-commands that change how traces are sourced. With no sub-command
+/// the tool batch-polls the GitHub API (the default behaviour).
+#[derive(Subcommand)]
+enum Command {
+    /// Run as a long-lived webhook server, tracing runs as their
+    /// `workflow_run`/`workflow_job` deliveries arrive.
+    Serve(ServeOpts),
+}
+
+/// Options for the `serve` webhook server mode.
+#[derive(Parser)]
+struct ServeOpts {
+    /// Address the webhook server binds to
+    #[clap(long, default_value = "0.0.0.0:8080")]
+    address: String,
+    /// Shared secret used to verify the `X-Hub-Signature-256` header.
+    /// Falls back to the `GITHUB_WEBHOOK_SECRET` environment variable.
+    #[clap(long)]
+    secret: Option<String>,
+}
+
+/// Supported trace exporter backends. Each variant maps to the
+/// corresponding OpenTelemetry exporter pipeline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Exporter {
+    Jaeger,
+    Otlp,
+    Zipkin,
+    Datadog,
+    Stdout,
+}
+
+/// Jaeger transport selection. The agent is a single UDP I/O resource
+/// (subject to packet-size limits), while the collector speaks HTTP and
+/// can be multiplexed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum JaegerMode {
+    Agent,
+    Collector,
+}
+
+/// Jaeger-specific transport configuration threaded into
+/// [`build_tracer_provider`].
+struct JaegerConfig {
+    mode: JaegerMode,
+    agent_endpoint: Option<String>,
+    collector_endpoint: Option<String>,
+    collector_user: Option<String>,
+    collector_password: Option<String>,
+}
+
+/// Histogram instruments recording CI run and job durations (in
+/// seconds), labeled so users can chart p50/p95 per workflow/job and
+/// alert on regressions.
+struct Metrics {
+    // The periodic-reader controller must outlive the histograms, or its
+    // background export task is dropped and nothing is ever emitted — the
+    // same reason `main` keeps `trace_provider` alive for the trace path.
+    controller: opentelemetry::sdk::metrics::controllers::BasicController,
+    run_duration: Histogram<f64>,
+    job_duration: Histogram<f64>,
+}
+
+impl Metrics {
+    /// Stands up an OTLP metrics pipeline and derives the run/job
+    /// duration histograms from its meter.
+    fn new(endpoint: Option<String>) -> Result<Self> {
+        let mut exporter = opentelemetry_otlp::new_exporter().tonic();
+        if let Some(endpoint) = endpoint {
+            exporter = exporter.with_endpoint(endpoint);
+        }
+        let controller = opentelemetry_otlp::new_pipeline()
+            .metrics(opentelemetry::runtime::Tokio)
+            .with_exporter(exporter)
+            .build()?;
+
+        let meter = controller.meter("gh-action-trace");
+        Ok(Self {
+            run_duration: meter
+                .f64_histogram("ci.run.duration")
+                .with_unit(Unit::new("s"))
+                .with_description("Wall-clock duration of a workflow run")
+                .init(),
+            job_duration: meter
+                .f64_histogram("ci.job.duration")
+                .with_unit(Unit::new("s"))
+                .with_description("Wall-clock duration of a workflow job")
+                .init(),
+            controller,
+        })
+    }
+
+    /// Stops the controller, forcing a final collect-and-export so a
+    /// one-shot invocation doesn't exit before the periodic reader fires.
+    fn flush(&self) {
+        if let Err(e) = self.controller.stop(&opentelemetry::Context::current()) {
+            println!("Err flushing metrics: {}", e);
+        }
+    }
+}
+
+/// Builds a [`TracerProvider`] for the requested backend, wiring the
+/// `{owner}/{repo}` service name through as a resource. Every branch
+/// constructs the backend's pipeline and hands back a concrete
+/// `sdktrace::TracerProvider` so the rest of `main` is exporter-agnostic.
+///
+/// The Jaeger branch hands the service name to its own pipeline rather
+/// than attaching the resource. The Datadog exporter owns the service
+/// name itself and filters `SERVICE_NAME` out of the resource, so that
+/// branch also sets it on the pipeline instead of on the resource.
+fn build_tracer_provider(
+    exporter: Exporter,
+    endpoint: Option<String>,
+    service_name: String,
+    jaeger: JaegerConfig,
+) -> Result<sdktrace::TracerProvider> {
+    let config =
+        sdktrace::config().with_resource(Resource::new(vec![KeyValue::new(
+            SERVICE_NAME,
+            service_name.clone(),
+        )]));
+
+    let provider = match exporter {
+        Exporter::Jaeger => {
+            let mut pipeline =
+                opentelemetry_jaeger::new_pipeline().with_service_name(service_name);
+            match jaeger.mode {
+                // Agent: a single UDP I/O resource. Falls back to the
+                // generic `--endpoint` when `--agent-endpoint` is unset.
+                JaegerMode::Agent => {
+                    if let Some(endpoint) = jaeger.agent_endpoint.or(endpoint) {
+                        pipeline = pipeline.with_agent_endpoint(endpoint);
+                    }
+                }
+                // Collector: HTTP transport, optionally basic-authed.
+                JaegerMode::Collector => {
+                    if let Some(endpoint) = jaeger.collector_endpoint.or(endpoint) {
+                        pipeline = pipeline.with_collector_endpoint(endpoint);
+                    }
+                    if let Some(user) = jaeger.collector_user {
+                        pipeline = pipeline.with_collector_username(user);
+                    }
+                    if let Some(password) = jaeger.collector_password {
+                        pipeline = pipeline.with_collector_password(password);
+                    }
+                }
+            }
+            // Export asynchronously in batches through the decoupled
+            // uploader task so large repos don't block the runtime or
+            // overrun UDP packet limits.
+            pipeline.build_batch(opentelemetry::runtime::Tokio)?
+        }
+        Exporter::Otlp => {
+            let mut exporter = opentelemetry_otlp::new_exporter().tonic();
+            if let Some(endpoint) = endpoint {
+                exporter = exporter.with_endpoint(endpoint);
+            }
+            sdktrace::TracerProvider::builder()
+                .with_simple_exporter(opentelemetry_otlp::SpanExporterBuilder::from(exporter).build_span_exporter()?)
+                .with_config(config)
+                .build()
+        }
+        Exporter::Zipkin => {
+            let mut pipeline = opentelemetry_zipkin::new_pipeline();
+            if let Some(endpoint) = endpoint {
+                pipeline = pipeline.with_collector_endpoint(endpoint);
+            }
+            let exporter = pipeline.init_exporter()?;
+            sdktrace::TracerProvider::builder()
+                .with_simple_exporter(exporter)
+                .with_config(config)
+                .build()
+        }
+        Exporter::Datadog => {
+            // The Datadog exporter filters out SERVICE_NAME from the
+            // resource and sets it separately, so feed the service name
+            // through the pipeline rather than the resource.
+            let mut pipeline = opentelemetry_datadog::new_pipeline().with_service_name(service_name);
+            if let Some(endpoint) = endpoint {
+                pipeline = pipeline.with_agent_endpoint(endpoint);
+            }
+            pipeline.build_exporter().map(|exporter| {
+                sdktrace::TracerProvider::builder()
+                    .with_simple_exporter(exporter)
+                    .build()
+            })?
+        }
+        Exporter::Stdout => sdktrace::TracerProvider::builder()
+            .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
+            .with_config(config)
+            .build(),
+    };
+
+    Ok(provider)
 }
 
 #[tokio::main]
@@ -54,14 +318,60 @@ async fn main() -> Result<()> {
             .build()?;
     }
 
-    // Install a new OpenTelemetry trace pipeline
-    //let tracer = stdout::new_pipeline().install_simple();
-    let trace_provider = opentelemetry_jaeger::new_pipeline()
-        .with_service_name(format!("{}/{}", opts.owner, opts.repo))
-        .build_simple()?;
+    // Install a new OpenTelemetry trace pipeline for the selected backend
+    let trace_provider = build_tracer_provider(
+        opts.exporter,
+        opts.endpoint.clone(),
+        format!("{}/{}", opts.owner, opts.repo),
+        JaegerConfig {
+            mode: opts.jaeger_mode,
+            agent_endpoint: opts.agent_endpoint.clone(),
+            collector_endpoint: opts.collector_endpoint.clone(),
+            collector_user: opts.collector_user.clone(),
+            collector_password: opts.collector_password.clone(),
+        },
+    )?;
 
     let tracer = trace_provider.tracer("gh-action-trace", Some(env!("CARGO_PKG_VERSION")));
 
+    // In `serve` mode we don't poll the API at all; instead we stand up a
+    // webhook server that builds the same spans from incoming deliveries.
+    if let Some(Command::Serve(serve_opts)) = opts.command {
+        if opts.metrics {
+            anyhow::bail!("--metrics is not supported in `serve` mode");
+        }
+        return serve(serve_opts, tracer).await;
+    }
+
+    // Open the local cache, if one was requested. Its newest stored run
+    // bounds the fetch so only fresh runs hit the API.
+    let db = match &opts.db {
+        Some(path) => Some(Db::open(path)?),
+        None => None,
+    };
+
+    // Cache-only mode: rebuild traces from the local DB, no API calls.
+    if opts.from_cache {
+        let db = db.as_ref().expect("--from-cache requires --db");
+        export_from_cache(&tracer, db)?;
+        // Flush the (batched) exporter before the process exits.
+        trace_provider.force_flush();
+        return Ok(());
+    }
+
+    let created_filter = match &db {
+        Some(db) => db
+            .max_run_created_at()?
+            .map(|created| format!(">{}", created.to_rfc3339())),
+        None => None,
+    };
+
+    // Optionally stand up the duration-metrics pipeline alongside traces.
+    let metrics = match opts.metrics {
+        true => Some(Metrics::new(opts.metrics_endpoint.clone())?),
+        false => None,
+    };
+
     // List workflows
     let workflows = instance
         .workflows(opts.owner.clone(), opts.repo.clone())
@@ -71,86 +381,783 @@ async fn main() -> Result<()> {
         .into_iter();
 
     for (i, workflow) in workflows.clone().enumerate() {
-        // TODO: Process more runs
-        let runs = instance
-            .workflows(opts.owner.clone(), opts.repo.clone())
-            .list_runs(workflow.id.to_string())
-            //.exclude_pull_requests(true)
-            .send()
-            .await?;
-        let pb = ProgressBar::new(runs.items.len() as u64)
+        // Metrics are recorded only for the workflows named by
+        // `--workflow` (all of them when the filter is empty).
+        let workflow_metrics = metrics.as_ref().filter(|_| {
+            opts.workflows.is_empty() || opts.workflows.contains(&workflow.name)
+        });
+
+        // Walk every page of runs for this workflow, not just the first,
+        // so the trace covers the repo's full history.
+        let runs = all_runs(
+            &instance,
+            &opts.owner,
+            &opts.repo,
+            workflow.id.to_string(),
+            created_filter.clone(),
+        )
+        .await?;
+
+        let pb = ProgressBar::new(runs.len() as u64)
             .with_style(spinner_style.clone())
             .with_prefix(format!("[{}/{}]", i + 1, workflows.len()))
             .with_message(format!(
-                "Processing {} runs out of {} for workflow {}",
-                runs.items.len(),
-                runs.total_count.unwrap_or(0),
+                "Processing {} runs for workflow {}",
+                runs.len(),
                 workflow.name,
             ));
 
-        // List Jobs for each workflow
-        for run in runs {
-            let job_result = instance
-                .workflows(opts.owner.clone(), opts.repo.clone())
-                .list_jobs(run.id)
-                .send()
-                .await;
-
-            if let Err(_) = job_result {
-                println!("Err retrieving jobs for {} workflow run", run.id);
-                continue;
+        // Fetch jobs for each run with bounded parallelism: keep at most
+        // `concurrency` `list_jobs` requests in flight at once and build
+        // the trace for each run as its jobs land.
+        let mut in_flight = FuturesUnordered::new();
+        let mut runs_iter = runs.into_iter();
+        for _ in 0..opts.concurrency {
+            match runs_iter.next() {
+                Some(run) => in_flight.push(fetch_jobs(&instance, &opts.owner, &opts.repo, run)),
+                None => break,
             }
+        }
 
-            let mut last_end_time = run.created_at;
-
-            // Send a Trace for this Run
-            for job in job_result.unwrap() {
-                // Send a span for each job
-                let mut builder = tracer
-                    .span_builder(job.name.clone())
-                    .with_span_id(opentelemetry::trace::SpanId::from_hex(
-                        job.id.to_string().as_str(),
-                    ))
-                    .with_trace_id(opentelemetry::trace::TraceId::from_hex(
-                        run.id.to_string().as_str(),
-                    ))
-                    .with_start_time(job.started_at)
-                    .with_attributes(value_to_vec(&serde_json::to_value(&job).unwrap()))
-                    .with_status_message(job.status.to_string());
-                // Attach end time only if its not None
-                if let Some(completed_at) = job.completed_at {
-                    builder = builder.with_end_time(completed_at);
-                }
+        while let Some((run, job_result)) = in_flight.next().await {
+            if let Some(run) = runs_iter.next() {
+                in_flight.push(fetch_jobs(&instance, &opts.owner, &opts.repo, run));
+            }
 
-                tracer.build(builder);
+            let jobs = match job_result {
+                Ok(jobs) => jobs.items,
+                Err(_) => {
+                    println!("Err retrieving jobs for {} workflow run", run.id);
+                    pb.inc(1);
+                    continue;
+                }
+            };
 
-                // Update last_end_time
-                if let Some(completed_at) = job.completed_at {
-                    if completed_at > last_end_time {
-                        last_end_time = completed_at;
-                    }
+            if let Some(db) = &db {
+                if let Err(e) = db.persist(&workflow, &run, &jobs) {
+                    println!("Err caching run {} metadata: {}", run.id, e);
                 }
-                // TODO: Send a span for each step?
             }
 
-            let builder = tracer
-                .span_builder(run.name.clone())
-                .with_span_id(opentelemetry::trace::SpanId::from_hex(
-                    run.id.to_string().as_str(),
-                ))
+            build_run_trace(&tracer, &run, &jobs);
+            if let Some(metrics) = workflow_metrics {
+                record_metrics(metrics, &workflow.name, &run, &jobs);
+            }
+            pb.inc(1);
+        }
+
+        pb.finish_with_message(format!("Completed workflow {}", workflow.name));
+    }
+
+    // Force the batched exporter to upload any queued spans before the
+    // process exits; otherwise a short run can finish before the batch
+    // interval fires and emit nothing.
+    trace_provider.force_flush();
+    if let Some(metrics) = &metrics {
+        metrics.flush();
+    }
+    return Ok(());
+}
+
+/// Records run and job duration histograms for a single run, labeled by
+/// workflow name, job name, and conclusion. Durations come from the same
+/// `started_at`/`completed_at` fields used to build the spans.
+fn record_metrics(
+    metrics: &Metrics,
+    workflow_name: &str,
+    run: &octocrab::models::workflows::Run,
+    jobs: &[octocrab::models::workflows::Job],
+) {
+    let mut run_end = run.created_at;
+    for job in jobs {
+        if let Some(completed_at) = job.completed_at {
+            if completed_at > run_end {
+                run_end = completed_at;
+            }
+            let seconds = (completed_at - job.started_at).num_milliseconds() as f64 / 1000.0;
+            metrics.job_duration.record(
+                &opentelemetry::Context::current(),
+                seconds,
+                &[
+                    KeyValue::new("workflow", workflow_name.to_string()),
+                    KeyValue::new("job", job.name.clone()),
+                    KeyValue::new("conclusion", job.conclusion.clone().unwrap_or_default()),
+                ],
+            );
+        }
+    }
+
+    let seconds = (run_end - run.created_at).num_milliseconds() as f64 / 1000.0;
+    metrics.run_duration.record(
+        &opentelemetry::Context::current(),
+        seconds,
+        &[
+            KeyValue::new("workflow", workflow_name.to_string()),
+            KeyValue::new("conclusion", run.conclusion.clone().unwrap_or_default()),
+        ],
+    );
+}
+
+/// Walks octocrab's [`Page`] pagination to collect every workflow run,
+/// following `page.next` until the API stops handing back a next link.
+async fn all_runs(
+    instance: &octocrab::Octocrab,
+    owner: &str,
+    repo: &str,
+    workflow_id: String,
+    created: Option<String>,
+) -> Result<Vec<octocrab::models::workflows::Run>> {
+    let mut builder = instance
+        .workflows(owner.to_string(), repo.to_string())
+        .list_runs(workflow_id);
+    //.exclude_pull_requests(true)
+    if let Some(created) = created {
+        builder = builder.created(created);
+    }
+    let mut page = builder.send().await?;
+
+    let mut runs = Vec::new();
+    runs.append(&mut page.items);
+    while let Some(next) = page.next.clone() {
+        page = match instance.get_page(&Some(next)).await? {
+            Some(page) => page,
+            None => break,
+        };
+        runs.append(&mut page.items);
+    }
+
+    Ok(runs)
+}
+
+/// Fetches the jobs for a single run, pairing the result back up with the
+/// run so callers draining a [`FuturesUnordered`] still know which run the
+/// jobs belong to.
+async fn fetch_jobs(
+    instance: &octocrab::Octocrab,
+    owner: &str,
+    repo: &str,
+    run: octocrab::models::workflows::Run,
+) -> (
+    octocrab::models::workflows::Run,
+    octocrab::Result<octocrab::Page<octocrab::models::workflows::Job>>,
+) {
+    let job_result = instance
+        .workflows(owner.to_string(), repo.to_string())
+        .list_jobs(run.id)
+        .send()
+        .await;
+    (run, job_result)
+}
+
+/// Builds the run → job → step span hierarchy for a single workflow run
+/// and hands it to the tracer.
+fn build_run_trace<T: Tracer>(
+    tracer: &T,
+    run: &octocrab::models::workflows::Run,
+    jobs: &[octocrab::models::workflows::Job],
+) where
+    T::Span: Send + Sync + 'static,
+{
+    let mut last_end_time = run.created_at;
+
+    // Parent context for the job spans: the run's own span id, so jobs
+    // nest under the run rather than rendering as sibling trace roots.
+    let run_context = opentelemetry::Context::current().with_remote_span_context(
+        opentelemetry::trace::SpanContext::new(
+            opentelemetry::trace::TraceId::from_hex(run.id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::TraceId::INVALID),
+            opentelemetry::trace::SpanId::from_hex(run.id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::SpanId::INVALID),
+            opentelemetry::trace::TraceFlags::SAMPLED,
+            true,
+            opentelemetry::trace::TraceState::default(),
+        ),
+    );
+
+    for job in jobs {
+        // Send a span for each job
+        let mut builder = tracer
+            .span_builder(job.name.clone())
+            .with_span_id(opentelemetry::trace::SpanId::from_hex(
+                job.id.to_string().as_str(),
+            ))
+            .with_trace_id(opentelemetry::trace::TraceId::from_hex(
+                run.id.to_string().as_str(),
+            ))
+            .with_start_time(job.started_at)
+            .with_attributes(value_to_vec(&serde_json::to_value(&job).unwrap()))
+            .with_status_message(job.status.to_string());
+        // Attach end time only if its not None
+        if let Some(completed_at) = job.completed_at {
+            builder = builder.with_end_time(completed_at);
+        }
+
+        tracer.build_with_context(builder, &run_context);
+
+        // Update last_end_time
+        if let Some(completed_at) = job.completed_at {
+            if completed_at > last_end_time {
+                last_end_time = completed_at;
+            }
+        }
+
+        // Send a span for each step, parented under the job span.
+        // The child span id is derived from the job id and the
+        // step number so it stays stable across re-runs.
+        let job_context = opentelemetry::Context::current().with_remote_span_context(
+            opentelemetry::trace::SpanContext::new(
+                opentelemetry::trace::TraceId::from_hex(run.id.to_string().as_str())
+                    .unwrap_or(opentelemetry::trace::TraceId::INVALID),
+                opentelemetry::trace::SpanId::from_hex(job.id.to_string().as_str())
+                    .unwrap_or(opentelemetry::trace::SpanId::INVALID),
+                opentelemetry::trace::TraceFlags::SAMPLED,
+                true,
+                opentelemetry::trace::TraceState::default(),
+            ),
+        );
+
+        for step in &job.steps {
+            let mut builder = tracer
+                .span_builder(step.name.clone())
+                .with_span_id(step_span_id(job.id, step.number))
                 .with_trace_id(opentelemetry::trace::TraceId::from_hex(
                     run.id.to_string().as_str(),
                 ))
-                .with_start_time(run.created_at)
-                .with_end_time(last_end_time)
-                .with_attributes(value_to_vec(&serde_json::to_value(&run).unwrap()));
+                .with_start_time(step.started_at)
+                .with_attributes(value_to_vec(&serde_json::to_value(step).unwrap()))
+                .with_status_message(step.conclusion.clone().unwrap_or_default());
+            // Attach end time only if its not None
+            if let Some(completed_at) = step.completed_at {
+                builder = builder.with_end_time(completed_at);
+            }
 
-            tracer.build(builder);
-            pb.inc(1);
+            tracer.build_with_context(builder, &job_context);
         }
-        pb.finish_with_message(format!("Completed workflow {}", workflow.name));
     }
-    return Ok(());
+
+    let builder = tracer
+        .span_builder(run.name.clone())
+        .with_span_id(opentelemetry::trace::SpanId::from_hex(
+            run.id.to_string().as_str(),
+        ))
+        .with_trace_id(opentelemetry::trace::TraceId::from_hex(
+            run.id.to_string().as_str(),
+        ))
+        .with_start_time(run.created_at)
+        .with_end_time(last_end_time)
+        .with_attributes(value_to_vec(&serde_json::to_value(&run).unwrap()));
+
+    tracer.build(builder);
+}
+
+// step_span_id derives a stable span id for a workflow step from its
+// parent job id and the step number, so the same step always maps to
+// the same span across re-exports.
+fn step_span_id(job_id: octocrab::models::workflows::JobId, step_number: i64) -> opentelemetry::trace::SpanId {
+    step_span_id_raw(u64::from(job_id), step_number)
+}
+
+// step_span_id_raw is the numeric core of step_span_id, taking a plain
+// job id so the webhook path (which only has raw ids from the payload)
+// can derive the same stable span ids as the batch path.
+fn step_span_id_raw(job_id: u64, step_number: i64) -> opentelemetry::trace::SpanId {
+    let id = job_id.wrapping_mul(1000).wrapping_add(step_number as u64);
+    opentelemetry::trace::SpanId::from_hex(format!("{:016x}", id).as_str())
+        .unwrap_or(opentelemetry::trace::SpanId::INVALID)
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Shared state handed to every webhook request: the configured secret,
+/// the tracer used to emit spans, and the buffer of in-flight runs.
+struct AppState {
+    secret: String,
+    tracer: sdktrace::Tracer,
+    // run id -> accumulated run state. A run stays here until its
+    // `completed` delivery arrives, at which point the parent span is
+    // flushed and the entry dropped, or until the periodic sweep evicts
+    // it after `RUN_BUFFER_TTL` without a delivery.
+    runs: Mutex<HashMap<u64, RunBuffer>>,
+}
+
+/// How long a buffered run may sit without a new delivery before it's
+/// evicted. Guards against runs whose `completed` event never arrives
+/// (dropped/retried-away deliveries) leaking the map forever.
+const RUN_BUFFER_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Per-run accumulator. Job-completed deliveries arrive before the
+/// run-completed one, so we track the latest observed job end time here
+/// and use it as the parent run span's end time once the run completes.
+struct RunBuffer {
+    max_end_time: DateTime<Utc>,
+    // Monotonic time of the last delivery touching this run, used by the
+    // periodic sweep to evict runs that never complete.
+    last_updated: Instant,
+}
+
+#[derive(serde::Deserialize)]
+struct WorkflowJobEvent {
+    action: String,
+    workflow_job: WebhookJob,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct WebhookJob {
+    id: u64,
+    run_id: u64,
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    steps: Vec<WebhookStep>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct WebhookStep {
+    number: i64,
+    name: String,
+    conclusion: Option<String>,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(serde::Deserialize)]
+struct WorkflowRunEvent {
+    action: String,
+    workflow_run: WebhookRun,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct WebhookRun {
+    id: u64,
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Stands up the webhook server and blocks serving it.
+async fn serve(opts: ServeOpts, tracer: sdktrace::Tracer) -> Result<()> {
+    let secret = opts
+        .secret
+        .or_else(|| std::env::var("GITHUB_WEBHOOK_SECRET").ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!("a webhook secret is required (--secret or GITHUB_WEBHOOK_SECRET)")
+        })?;
+
+    let state = Arc::new(AppState {
+        secret,
+        tracer,
+        runs: Mutex::new(HashMap::new()),
+    });
+
+    // Periodically evict runs whose `completed` event never arrived so
+    // the in-flight map stays bounded on a long-lived server.
+    let sweep_state = state.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(RUN_BUFFER_TTL);
+        loop {
+            ticker.tick().await;
+            let mut runs = sweep_state.runs.lock().unwrap();
+            runs.retain(|_, buffer| buffer.last_updated.elapsed() < RUN_BUFFER_TTL);
+        }
+    });
+
+    let app = Router::new()
+        .route("/", post(webhook))
+        .with_state(state);
+
+    let addr = opts.address.parse()?;
+    println!("Listening for GitHub webhooks on {}", opts.address);
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
+
+/// Handles a single webhook delivery: verifies the signature, then routes
+/// the payload to the matching span-building logic.
+async fn webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let signature = headers
+        .get("X-Hub-Signature-256")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_signature(&state.secret, &body, signature) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let event = headers
+        .get("X-GitHub-Event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    match event {
+        "workflow_job" => match serde_json::from_slice::<WorkflowJobEvent>(&body) {
+            Ok(event) => handle_job_event(&state, event),
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        "workflow_run" => match serde_json::from_slice::<WorkflowRunEvent>(&body) {
+            Ok(event) => handle_run_event(&state, event),
+            Err(_) => return StatusCode::BAD_REQUEST,
+        },
+        // Deliveries we don't trace (ping, etc.) are acknowledged so
+        // GitHub marks the hook healthy.
+        _ => {}
+    }
+
+    StatusCode::OK
+}
+
+/// Verifies the `X-Hub-Signature-256` header (`sha256=<hex>`) with an
+/// HMAC-SHA256 over the raw request body, in constant time.
+fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let hex = match signature.strip_prefix("sha256=") {
+        Some(hex) => hex,
+        None => return false,
+    };
+    let expected = match hex::decode(hex) {
+        Ok(bytes) => bytes,
+        Err(_) => return false,
+    };
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Emits the job (and step) spans for a completed `workflow_job` delivery
+/// and records its end time against the buffered run.
+fn handle_job_event(state: &AppState, event: WorkflowJobEvent) {
+    // Only completed jobs have the end times we need for a span.
+    if event.action != "completed" {
+        return;
+    }
+    let job = event.workflow_job;
+    build_webhook_job_span(&state.tracer, &job);
+
+    if let Some(completed_at) = job.completed_at {
+        let mut runs = state.runs.lock().unwrap();
+        let buffer = runs.entry(job.run_id).or_insert(RunBuffer {
+            max_end_time: completed_at,
+            last_updated: Instant::now(),
+        });
+        if completed_at > buffer.max_end_time {
+            buffer.max_end_time = completed_at;
+        }
+        buffer.last_updated = Instant::now();
+    }
+}
+
+/// Flushes the parent run span once the `workflow_run` delivery reports
+/// the run as completed, using the end time accumulated from its jobs.
+fn handle_run_event(state: &AppState, event: WorkflowRunEvent) {
+    let run = event.workflow_run;
+    // The parent span is flushed on `completed`; the run's own payload
+    // carries its name and created time, so in-progress deliveries need
+    // no buffering beyond the job end times tracked on job events.
+    if event.action != "completed" {
+        return;
+    }
+
+    let buffer = state.runs.lock().unwrap().remove(&run.id);
+    let end_time = buffer
+        .as_ref()
+        .map(|b| b.max_end_time)
+        .unwrap_or(run.created_at);
+
+    let builder = state
+        .tracer
+        .span_builder(run.name.clone().unwrap_or_default())
+        .with_span_id(opentelemetry::trace::SpanId::from_hex(
+            run.id.to_string().as_str(),
+        ))
+        .with_trace_id(opentelemetry::trace::TraceId::from_hex(
+            run.id.to_string().as_str(),
+        ))
+        .with_start_time(run.created_at)
+        .with_end_time(end_time)
+        .with_attributes(value_to_vec(&serde_json::to_value(&run).unwrap()));
+    state.tracer.build(builder);
+}
+
+/// Builds a job span and its child step spans from a webhook payload,
+/// mirroring the run → job → step hierarchy produced in batch mode.
+fn build_webhook_job_span(tracer: &sdktrace::Tracer, job: &WebhookJob) {
+    let mut builder = tracer
+        .span_builder(job.name.clone())
+        .with_span_id(opentelemetry::trace::SpanId::from_hex(
+            job.id.to_string().as_str(),
+        ))
+        .with_trace_id(opentelemetry::trace::TraceId::from_hex(
+            job.run_id.to_string().as_str(),
+        ))
+        .with_start_time(job.started_at)
+        .with_attributes(value_to_vec(&serde_json::to_value(job).unwrap()))
+        .with_status_message(job.conclusion.clone().unwrap_or_else(|| job.status.clone()));
+    if let Some(completed_at) = job.completed_at {
+        builder = builder.with_end_time(completed_at);
+    }
+    // Parent the job under the run's span id so it nests under the run
+    // rather than rendering as a sibling trace root.
+    let run_context = opentelemetry::Context::current().with_remote_span_context(
+        opentelemetry::trace::SpanContext::new(
+            opentelemetry::trace::TraceId::from_hex(job.run_id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::TraceId::INVALID),
+            opentelemetry::trace::SpanId::from_hex(job.run_id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::SpanId::INVALID),
+            opentelemetry::trace::TraceFlags::SAMPLED,
+            true,
+            opentelemetry::trace::TraceState::default(),
+        ),
+    );
+    tracer.build_with_context(builder, &run_context);
+
+    let job_context = opentelemetry::Context::current().with_remote_span_context(
+        opentelemetry::trace::SpanContext::new(
+            opentelemetry::trace::TraceId::from_hex(job.run_id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::TraceId::INVALID),
+            opentelemetry::trace::SpanId::from_hex(job.id.to_string().as_str())
+                .unwrap_or(opentelemetry::trace::SpanId::INVALID),
+            opentelemetry::trace::TraceFlags::SAMPLED,
+            true,
+            opentelemetry::trace::TraceState::default(),
+        ),
+    );
+
+    for step in &job.steps {
+        let mut builder = tracer
+            .span_builder(step.name.clone())
+            .with_span_id(step_span_id_raw(job.id, step.number))
+            .with_trace_id(opentelemetry::trace::TraceId::from_hex(
+                job.run_id.to_string().as_str(),
+            ))
+            .with_attributes(value_to_vec(&serde_json::to_value(step).unwrap()))
+            .with_status_message(step.conclusion.clone().unwrap_or_default());
+        if let Some(started_at) = step.started_at {
+            builder = builder.with_start_time(started_at);
+        }
+        if let Some(completed_at) = step.completed_at {
+            builder = builder.with_end_time(completed_at);
+        }
+        tracer.build_with_context(builder, &job_context);
+    }
+}
+
+/// A thin SQLite cache of fetched workflow/run/job metadata, keyed by
+/// GitHub id. Mirrors the dbctx-style schema used by self-hosted CI
+/// dashboards so the same data can be re-exported without the API.
+struct Db {
+    conn: rusqlite::Connection,
+}
+
+impl Db {
+    /// Opens (creating if needed) the database at `path` and ensures the
+    /// schema exists.
+    fn open(path: &std::path::Path) -> Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS workflows (
+                 id   TEXT PRIMARY KEY,
+                 name TEXT
+             );
+             CREATE TABLE IF NOT EXISTS runs (
+                 id          TEXT PRIMARY KEY,
+                 workflow_id TEXT,
+                 name        TEXT,
+                 created_at  TEXT,
+                 conclusion  TEXT
+             );
+             CREATE TABLE IF NOT EXISTS jobs (
+                 id           TEXT PRIMARY KEY,
+                 run_id       TEXT,
+                 name         TEXT,
+                 started_at   TEXT,
+                 completed_at TEXT,
+                 status       TEXT,
+                 conclusion   TEXT
+             );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the newest `created_at` across all cached runs, used as the
+    /// lower bound for the next incremental fetch.
+    fn max_run_created_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let created: Option<String> = self
+            .conn
+            .query_row("SELECT MAX(created_at) FROM runs", [], |row| row.get(0))?;
+        match created {
+            Some(created) => Ok(Some(
+                DateTime::parse_from_rfc3339(&created)?.with_timezone(&Utc),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Upserts a workflow, one of its runs, and that run's jobs.
+    fn persist(
+        &self,
+        workflow: &octocrab::models::workflows::WorkFlow,
+        run: &octocrab::models::workflows::Run,
+        jobs: &[octocrab::models::workflows::Job],
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO workflows (id, name) VALUES (?1, ?2)",
+            rusqlite::params![workflow.id.to_string(), workflow.name],
+        )?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO runs (id, workflow_id, name, created_at, conclusion)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                run.id.to_string(),
+                workflow.id.to_string(),
+                run.name,
+                run.created_at.to_rfc3339(),
+                run.conclusion,
+            ],
+        )?;
+        for job in jobs {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO jobs
+                     (id, run_id, name, started_at, completed_at, status, conclusion)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    job.id.to_string(),
+                    run.id.to_string(),
+                    job.name,
+                    job.started_at.to_rfc3339(),
+                    job.completed_at.map(|c| c.to_rfc3339()),
+                    job.status.to_string(),
+                    job.conclusion,
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Loads every cached run, oldest first, for cache-only re-export.
+    fn runs(&self) -> Result<Vec<CachedRun>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, created_at, conclusion FROM runs ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(CachedRun {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                conclusion: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Loads the cached jobs belonging to a single run, in a stable order.
+    fn jobs_for(&self, run_id: &str) -> Result<Vec<CachedJob>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, started_at, completed_at, status, conclusion
+             FROM jobs WHERE run_id = ?1 ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map([run_id], |row| {
+            Ok(CachedJob {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                started_at: row.get(2)?,
+                completed_at: row.get(3)?,
+                status: row.get(4)?,
+                conclusion: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+}
+
+/// A run as stored in the local cache. Ids stay as their textual GitHub
+/// form so they can be fed straight back to `SpanId`/`TraceId::from_hex`.
+struct CachedRun {
+    id: String,
+    name: Option<String>,
+    created_at: String,
+    conclusion: Option<String>,
+}
+
+/// A job as stored in the local cache. `id` is the real GitHub job id in
+/// textual form, reused verbatim so cache re-exports reproduce the same
+/// span ids the live path emitted.
+struct CachedJob {
+    id: String,
+    name: String,
+    started_at: String,
+    completed_at: Option<String>,
+    status: String,
+    conclusion: Option<String>,
+}
+
+/// Rebuilds and re-exports run → job traces straight from the cache,
+/// without touching the GitHub API. Steps aren't cached, so cache-only
+/// exports reconstruct the run and job levels only.
+fn export_from_cache(tracer: &sdktrace::Tracer, db: &Db) -> Result<()> {
+    for run in db.runs()? {
+        let created_at = DateTime::parse_from_rfc3339(&run.created_at)?.with_timezone(&Utc);
+        let mut last_end_time = created_at;
+
+        let run_context = opentelemetry::Context::current().with_remote_span_context(
+            opentelemetry::trace::SpanContext::new(
+                opentelemetry::trace::TraceId::from_hex(run.id.as_str())
+                    .unwrap_or(opentelemetry::trace::TraceId::INVALID),
+                opentelemetry::trace::SpanId::from_hex(run.id.as_str())
+                    .unwrap_or(opentelemetry::trace::SpanId::INVALID),
+                opentelemetry::trace::TraceFlags::SAMPLED,
+                true,
+                opentelemetry::trace::TraceState::default(),
+            ),
+        );
+
+        for job in db.jobs_for(&run.id)? {
+            let started_at = DateTime::parse_from_rfc3339(&job.started_at)?.with_timezone(&Utc);
+            // Reuse the real GitHub job id so the span id matches the one
+            // the live/batch path emitted for this job.
+            let mut builder = tracer
+                .span_builder(job.name)
+                .with_span_id(opentelemetry::trace::SpanId::from_hex(job.id.as_str()))
+                .with_trace_id(opentelemetry::trace::TraceId::from_hex(run.id.as_str()))
+                .with_start_time(started_at)
+                .with_status_message(job.conclusion.unwrap_or(job.status));
+            if let Some(completed_at) = job.completed_at {
+                let completed_at =
+                    DateTime::parse_from_rfc3339(&completed_at)?.with_timezone(&Utc);
+                if completed_at > last_end_time {
+                    last_end_time = completed_at;
+                }
+                builder = builder.with_end_time(completed_at);
+            }
+            tracer.build_with_context(builder, &run_context);
+        }
+
+        let builder = tracer
+            .span_builder(run.name.unwrap_or_default())
+            .with_span_id(opentelemetry::trace::SpanId::from_hex(run.id.as_str()))
+            .with_trace_id(opentelemetry::trace::TraceId::from_hex(run.id.as_str()))
+            .with_start_time(created_at)
+            .with_end_time(last_end_time)
+            .with_status_message(run.conclusion.unwrap_or_default());
+        tracer.build(builder);
+    }
+    Ok(())
+}
+
+// parse_concurrency rejects a `--concurrency 0`, which would otherwise
+// leave the in-flight set empty and silently skip every run.
+fn parse_concurrency(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_string()),
+        Ok(n) => Ok(n),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 // value_to_vec converts a serde Value into a Vec of KeyValue
@@ -166,3 +1173,53 @@ fn value_to_vec(value: &serde_json::Value) -> Vec<KeyValue> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_span_id_is_stable_and_distinct() {
+        // Same job id + step number always maps to the same span id.
+        assert_eq!(step_span_id_raw(42, 1), step_span_id_raw(42, 1));
+        // Different steps of the same job get different span ids.
+        assert_ne!(step_span_id_raw(42, 1), step_span_id_raw(42, 2));
+        // The same step number under different jobs stays distinct.
+        assert_ne!(step_span_id_raw(42, 1), step_span_id_raw(43, 1));
+    }
+
+    // Produces the `sha256=<hex>` header GitHub would send for `body`.
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn verify_signature_accepts_valid() {
+        let body = b"{\"action\":\"completed\"}";
+        let signature = sign("s3cr3t", body);
+        assert!(verify_signature("s3cr3t", body, &signature));
+    }
+
+    #[test]
+    fn verify_signature_rejects_bad_secret_and_tamper() {
+        let body = b"{\"action\":\"completed\"}";
+        let signature = sign("s3cr3t", body);
+        // Wrong secret.
+        assert!(!verify_signature("wrong", body, &signature));
+        // Body tampered after signing.
+        assert!(!verify_signature("s3cr3t", b"tampered", &signature));
+    }
+
+    #[test]
+    fn verify_signature_rejects_malformed_header() {
+        let body = b"payload";
+        // Missing `sha256=` prefix.
+        assert!(!verify_signature("s3cr3t", body, "deadbeef"));
+        // Empty header.
+        assert!(!verify_signature("s3cr3t", body, ""));
+        // Non-hex after the prefix.
+        assert!(!verify_signature("s3cr3t", body, "sha256=nothex"));
+    }
+}